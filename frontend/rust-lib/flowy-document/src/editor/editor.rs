@@ -17,7 +17,6 @@ use tokio::sync::{mpsc, oneshot};
 use ws_model::ws_revision::ServerRevisionWSData;
 
 pub struct AppFlowyDocumentEditor {
-  #[allow(dead_code)]
   doc_id: String,
   command_sender: CommandSender,
   rev_manager: Arc<RevisionManager<Arc<ConnectionPool>>>,
@@ -44,6 +43,7 @@ impl AppFlowyDocumentEditor {
     Ok(editor)
   }
 
+  #[tracing::instrument(level = "trace", skip_all, fields(doc_id = %self.doc_id), err)]
   pub async fn apply_transaction(&self, transaction: Transaction) -> FlowyResult<()> {
     let (ret, rx) = oneshot::channel::<FlowyResult<()>>();
     let _ = self
@@ -54,6 +54,7 @@ impl AppFlowyDocumentEditor {
     Ok(())
   }
 
+  #[tracing::instrument(level = "trace", skip(self), fields(doc_id = %self.doc_id, bytes), err)]
   pub async fn get_content(&self, pretty: bool) -> FlowyResult<String> {
     let (ret, rx) = oneshot::channel::<FlowyResult<String>>();
     let _ = self
@@ -61,9 +62,15 @@ impl AppFlowyDocumentEditor {
       .send(Command::GetDocumentContent { pretty, ret })
       .await;
     let content = rx.await.map_err(internal_error)??;
+    tracing::Span::current().record("bytes", content.len());
     Ok(content)
   }
 
+  /// Returns the id of the latest revision of the document.
+  pub fn rev_id(&self) -> i64 {
+    self.rev_manager.rev_id()
+  }
+
   pub async fn duplicate_document(&self) -> FlowyResult<String> {
     let transaction = self.document_transaction().await?;
     let document = Document::from_transaction(transaction)?;
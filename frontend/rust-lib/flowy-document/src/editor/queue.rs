@@ -11,12 +11,15 @@ use lib_ot::core::Transaction;
 use flowy_sqlite::ConnectionPool;
 use std::sync::Arc;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::{oneshot, Mutex, RwLock};
 
 pub struct DocumentQueue {
   #[allow(dead_code)]
   user: Arc<dyn DocumentUser>,
   document: Arc<RwLock<Document>>,
+  /// The md5 of the document content that was last persisted. Used to skip saving
+  /// revisions that don't change the content.
+  persisted_md5: Mutex<String>,
   #[allow(dead_code)]
   rev_manager: Arc<RevisionManager<Arc<ConnectionPool>>>,
   receiver: Option<CommandReceiver>,
@@ -29,10 +32,12 @@ impl DocumentQueue {
     document: Document,
     receiver: CommandReceiver,
   ) -> Self {
+    let persisted_md5 = Mutex::new(document.document_md5());
     let document = Arc::new(RwLock::new(document));
     Self {
       user,
       document,
+      persisted_md5,
       rev_manager,
       receiver: Some(receiver),
     }
@@ -61,14 +66,19 @@ impl DocumentQueue {
   async fn handle_command(&self, command: Command) -> Result<(), FlowyError> {
     match command {
       Command::ComposeTransaction { transaction, ret } => {
-        self
-          .document
-          .write()
-          .await
-          .apply_transaction(transaction.clone())?;
-        let _ = self
-          .save_local_operations(transaction, self.document.read().await.document_md5())
-          .await?;
+        let md5 = {
+          let mut document = self.document.write().await;
+          document.apply_transaction(transaction.clone())?;
+          document.document_md5()
+        };
+        // Skip saving the transaction if it doesn't change the content of the document.
+        let mut persisted_md5 = self.persisted_md5.lock().await;
+        if *persisted_md5 == md5 {
+          tracing::trace!("[DocumentQueue]: skip saving the transaction, content not changed");
+        } else {
+          let _ = self.save_local_operations(transaction, md5.clone()).await?;
+          *persisted_md5 = md5;
+        }
         let _ = ret.send(Ok(()));
       },
       Command::GetDocumentContent { pretty, ret } => {
@@ -79,13 +89,14 @@ impl DocumentQueue {
     Ok(())
   }
 
-  #[tracing::instrument(level = "trace", skip(self, transaction, md5), err)]
+  #[tracing::instrument(level = "trace", skip(self, transaction, md5), fields(bytes), err)]
   async fn save_local_operations(
     &self,
     transaction: Transaction,
     md5: String,
   ) -> Result<i64, FlowyError> {
     let bytes = Bytes::from(transaction.to_bytes()?);
+    tracing::Span::current().record("bytes", bytes.len());
     let rev_id = self.rev_manager.add_local_revision(bytes, md5).await?;
     Ok(rev_id)
   }
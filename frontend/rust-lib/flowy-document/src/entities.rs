@@ -50,6 +50,7 @@ pub struct EditParams {
 impl TryInto<EditParams> for EditPayloadPB {
   type Error = ErrorCode;
   fn try_into(self) -> Result<EditParams, Self::Error> {
+    validate_document_id(&self.doc_id)?;
     Ok(EditParams {
       doc_id: self.doc_id,
       operations: self.operations,
@@ -104,6 +105,23 @@ pub struct OpenDocumentPayloadPB {
   pub version: DocumentVersionPB,
 }
 
+#[derive(Default, Debug)]
+pub struct OpenDocumentParams {
+  pub document_id: String,
+  pub version: DocumentVersionPB,
+}
+
+impl TryInto<OpenDocumentParams> for OpenDocumentPayloadPB {
+  type Error = ErrorCode;
+  fn try_into(self) -> Result<OpenDocumentParams, Self::Error> {
+    validate_document_id(&self.document_id)?;
+    Ok(OpenDocumentParams {
+      document_id: self.document_id,
+      version: self.version,
+    })
+  }
+}
+
 #[derive(Default, Debug)]
 pub struct ExportParams {
   pub view_id: String,
@@ -114,6 +132,7 @@ pub struct ExportParams {
 impl TryInto<ExportParams> for ExportPayloadPB {
   type Error = ErrorCode;
   fn try_into(self) -> Result<ExportParams, Self::Error> {
+    validate_document_id(&self.view_id)?;
     Ok(ExportParams {
       view_id: self.view_id,
       export_type: self.export_type,
@@ -130,3 +149,19 @@ pub struct ExportDataPB {
   #[pb(index = 2)]
   pub export_type: ExportType,
 }
+
+/// The maximum length of a document id in bytes. Document ids are generated uuids, so
+/// anything close to this limit is a caller bug.
+pub const DOCUMENT_ID_MAX_LENGTH: usize = 256;
+
+/// Returns an error if the document id is empty, only contains whitespace, or is longer
+/// than [DOCUMENT_ID_MAX_LENGTH].
+pub(crate) fn validate_document_id(document_id: &str) -> Result<(), ErrorCode> {
+  if document_id.trim().is_empty() {
+    return Err(ErrorCode::DocumentIdIsEmpty);
+  }
+  if document_id.len() > DOCUMENT_ID_MAX_LENGTH {
+    return Err(ErrorCode::DocumentIdTooLong);
+  }
+  Ok(())
+}
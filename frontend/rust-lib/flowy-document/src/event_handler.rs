@@ -1,6 +1,6 @@
 use crate::entities::{
   DocumentDataPB, EditParams, EditPayloadPB, ExportDataPB, ExportParams, ExportPayloadPB,
-  OpenDocumentPayloadPB,
+  OpenDocumentParams, OpenDocumentPayloadPB,
 };
 use crate::DocumentManager;
 use flowy_error::FlowyError;
@@ -9,19 +9,25 @@ use lib_dispatch::prelude::{data_result_ok, AFPluginData, AFPluginState, DataRes
 use std::convert::TryInto;
 use std::sync::Arc;
 
+#[tracing::instrument(level = "trace", skip(data, manager), fields(doc_id, bytes), err)]
 pub(crate) async fn get_document_handler(
   data: AFPluginData<OpenDocumentPayloadPB>,
   manager: AFPluginState<Arc<DocumentManager>>,
 ) -> DataResult<DocumentDataPB, FlowyError> {
-  let context: OpenDocumentPayloadPB = data.into_inner();
-  let editor = manager.open_document_editor(&context.document_id).await?;
+  let payload = data.into_inner();
+  // Record the raw id before validating it, so rejected ids show up in the span as well.
+  tracing::Span::current().record("doc_id", payload.document_id.as_str());
+  let params: OpenDocumentParams = payload.try_into()?;
+  let editor = manager.open_document_editor(&params.document_id).await?;
   let document_data = editor.export().await?;
+  tracing::Span::current().record("bytes", document_data.len());
   data_result_ok(DocumentDataPB {
-    doc_id: context.document_id,
+    doc_id: params.document_id,
     content: document_data,
   })
 }
 
+#[tracing::instrument(level = "debug", skip(data, manager), err)]
 pub(crate) async fn apply_edit_handler(
   data: AFPluginData<EditPayloadPB>,
   manager: AFPluginState<Arc<DocumentManager>>,
@@ -1,5 +1,5 @@
 use crate::editor::{initial_document_content, AppFlowyDocumentEditor, DocumentRevisionMergeable};
-use crate::entities::{DocumentVersionPB, EditParams};
+use crate::entities::{validate_document_id, DocumentVersionPB, EditParams};
 use crate::old_editor::editor::{DeltaDocumentEditor, DeltaDocumentRevisionMergeable};
 use crate::old_editor::snapshot::DeltaDocumentSnapshotPersistence;
 use crate::services::rev_sqlite::{
@@ -9,9 +9,10 @@ use crate::services::rev_sqlite::{
 use crate::services::DocumentPersistence;
 use crate::{errors::FlowyError, DocumentCloudService};
 use bytes::Bytes;
+use dashmap::DashMap;
 use document_model::document::DocumentId;
 use flowy_client_sync::client_document::initial_delta_document_content;
-use flowy_error::FlowyResult;
+use flowy_error::{ErrorCode, FlowyResult};
 use flowy_revision::{
   RevisionCloudService, RevisionManager, RevisionPersistence, RevisionPersistenceConfiguration,
   RevisionWebSocket,
@@ -25,8 +26,9 @@ use lib_ws::WSConnectState;
 use revision_model::Revision;
 use std::any::Any;
 use std::convert::TryFrom;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 use ws_model::ws_revision::ServerRevisionWSData;
 
 pub trait DocumentUser: Send + Sync {
@@ -87,6 +89,8 @@ pub struct DocumentManager {
   cloud_service: Arc<dyn DocumentCloudService>,
   rev_web_socket: Arc<dyn RevisionWebSocket>,
   editor_map: Arc<RwLock<RefCountHashMap<RefCountDocumentHandler>>>,
+  /// The locks of the documents that are being opened, keyed by document id.
+  open_locks: DashMap<String, Arc<DocumentOpenLock>>,
   user: Arc<dyn DocumentUser>,
   persistence: Arc<DocumentPersistence>,
   #[allow(dead_code)]
@@ -105,6 +109,7 @@ impl DocumentManager {
       cloud_service,
       rev_web_socket,
       editor_map: Arc::new(RwLock::new(RefCountHashMap::new())),
+      open_locks: DashMap::new(),
       user: document_user,
       persistence: Arc::new(DocumentPersistence::new(database)),
       config,
@@ -137,10 +142,23 @@ impl DocumentManager {
   pub async fn close_document_editor<T: AsRef<str>>(&self, editor_id: T) -> Result<(), FlowyError> {
     let editor_id = editor_id.as_ref();
     tracing::Span::current().record("editor_id", editor_id);
-    self.editor_map.write().await.remove(editor_id).await;
+    validate_document_id(editor_id)?;
+    let mut editor_map = self.editor_map.write().await;
+    if editor_map.get(editor_id).is_some() {
+      editor_map.remove(editor_id).await;
+    } else if let Some(open_lock) = self.open_locks.get(editor_id) {
+      // The editor is still being initialized, so the close cancels the open.
+      open_lock.cancel();
+    }
     Ok(())
   }
 
+  #[tracing::instrument(
+    level = "trace",
+    skip_all,
+    fields(doc_id = %params.doc_id, bytes = params.operations.len()),
+    err
+  )]
   pub async fn apply_edit(&self, params: EditParams) -> FlowyResult<()> {
     let editor = self.get_document_editor(&params.doc_id).await?;
     editor
@@ -149,12 +167,19 @@ impl DocumentManager {
     Ok(())
   }
 
+  #[tracing::instrument(
+    level = "trace",
+    skip_all,
+    fields(doc_id = doc_id.as_ref(), revisions = revisions.len()),
+    err
+  )]
   pub async fn create_document<T: AsRef<str>>(
     &self,
     doc_id: T,
     revisions: Vec<Revision>,
   ) -> FlowyResult<()> {
     let doc_id = doc_id.as_ref().to_owned();
+    validate_document_id(&doc_id)?;
     let db_pool = self.persistence.database.db_pool()?;
     // Maybe we could save the document to disk without creating the RevisionManager
     let rev_manager = self.make_rev_manager(&doc_id, db_pool)?;
@@ -201,7 +226,8 @@ impl DocumentManager {
   /// returns: Result<Arc<DocumentEditor>, FlowyError>
   ///
   async fn get_document_editor(&self, doc_id: &str) -> FlowyResult<Arc<dyn DocumentEditor>> {
-    match self.editor_map.read().await.get(doc_id) {
+    let handler = self.editor_map.read().await.get(doc_id);
+    match handler {
       None => {
         //
         tracing::warn!("Should call init_document_editor first");
@@ -211,12 +237,12 @@ impl DocumentManager {
     }
   }
 
-  /// Initializes a document editor with the doc_id
+  /// Initializes a document editor with the doc_id. Returns the opened editor if the
+  /// document is already opened.
   ///
   /// # Arguments
   ///
   /// * `doc_id`: the id of the document
-  /// * `pool`: sqlite connection pool
   ///
   /// returns: Result<Arc<DocumentEditor>, FlowyError>
   ///
@@ -225,6 +251,55 @@ impl DocumentManager {
     &self,
     doc_id: &str,
   ) -> Result<Arc<dyn DocumentEditor>, FlowyError> {
+    validate_document_id(doc_id)?;
+    // Opening the same document is serialized, so the document only has one editor.
+    let open_lock = self
+      .open_locks
+      .entry(doc_id.to_owned())
+      .or_default()
+      .clone();
+    let result = {
+      let _guard = open_lock.lock.lock().await;
+      self.get_or_make_document_editor(doc_id, &open_lock).await
+    };
+    drop(open_lock);
+    // Remove the lock if no other open of the document is waiting for it.
+    self
+      .open_locks
+      .remove_if(doc_id, |_, open_lock| Arc::strong_count(open_lock) == 1);
+    result
+  }
+
+  async fn get_or_make_document_editor(
+    &self,
+    doc_id: &str,
+    open_lock: &DocumentOpenLock,
+  ) -> FlowyResult<Arc<dyn DocumentEditor>> {
+    {
+      let mut editor_map = self.editor_map.write().await;
+      if let Some(handler) = editor_map.get(doc_id) {
+        // Increase the ref count of the opened editor.
+        editor_map.insert(doc_id.to_string(), handler.clone());
+        return Ok(handler.0);
+      }
+      open_lock.start();
+    }
+
+    let result = self.make_document_editor(doc_id).await;
+    let mut editor_map = self.editor_map.write().await;
+    let cancelled = open_lock.finish();
+    let editor = result?;
+    if cancelled {
+      drop(editor_map);
+      tracing::trace!("{} was closed before it finished opening", doc_id);
+      editor.close().await;
+      return Err(ErrorCode::DocumentOpenCancelled.into());
+    }
+    editor_map.insert(doc_id.to_string(), RefCountDocumentHandler(editor.clone()));
+    Ok(editor)
+  }
+
+  async fn make_document_editor(&self, doc_id: &str) -> FlowyResult<Arc<dyn DocumentEditor>> {
     let pool = self.persistence.database.db_pool()?;
     let user = self.user.clone();
     let token = self.user.token()?;
@@ -235,34 +310,21 @@ impl DocumentManager {
 
     match self.config.version {
       DocumentVersionPB::V0 => {
-        let rev_manager = self.make_delta_document_rev_manager(doc_id, pool.clone())?;
-        let editor: Arc<dyn DocumentEditor> = Arc::new(
-          DeltaDocumentEditor::new(
-            doc_id,
-            user,
-            rev_manager,
-            self.rev_web_socket.clone(),
-            cloud_service,
-          )
-          .await?,
-        );
-        self
-          .editor_map
-          .write()
-          .await
-          .insert(doc_id.to_string(), RefCountDocumentHandler(editor.clone()));
-        Ok(editor)
+        let rev_manager = self.make_delta_document_rev_manager(doc_id, pool)?;
+        let editor = DeltaDocumentEditor::new(
+          doc_id,
+          user,
+          rev_manager,
+          self.rev_web_socket.clone(),
+          cloud_service,
+        )
+        .await?;
+        Ok(Arc::new(editor))
       },
       DocumentVersionPB::V1 => {
-        let rev_manager = self.make_document_rev_manager(doc_id, pool.clone())?;
-        let editor: Arc<dyn DocumentEditor> =
-          Arc::new(AppFlowyDocumentEditor::new(doc_id, user, rev_manager, cloud_service).await?);
-        self
-          .editor_map
-          .write()
-          .await
-          .insert(doc_id.to_string(), RefCountDocumentHandler(editor.clone()));
-        Ok(editor)
+        let rev_manager = self.make_document_rev_manager(doc_id, pool)?;
+        let editor = AppFlowyDocumentEditor::new(doc_id, user, rev_manager, cloud_service).await?;
+        Ok(Arc::new(editor))
       },
     }
   }
@@ -352,6 +414,41 @@ impl RevisionCloudService for DocumentRevisionCloudService {
   }
 }
 
+#[cfg(feature = "flowy_unit_test")]
+impl DocumentManager {
+  pub async fn is_document_opened(&self, doc_id: &str) -> bool {
+    self.editor_map.read().await.get(doc_id).is_some()
+  }
+}
+
+/// Serializes opening the same document and lets a close cancel the open while the editor
+/// is being initialized. The flags are only changed while holding the `editor_map` write lock.
+#[derive(Default)]
+struct DocumentOpenLock {
+  lock: Mutex<()>,
+  initializing: AtomicBool,
+  cancelled: AtomicBool,
+}
+
+impl DocumentOpenLock {
+  fn start(&self) {
+    self.cancelled.store(false, Ordering::SeqCst);
+    self.initializing.store(true, Ordering::SeqCst);
+  }
+
+  /// Returns true if the open was cancelled while the editor was being initialized.
+  fn finish(&self) -> bool {
+    self.initializing.store(false, Ordering::SeqCst);
+    self.cancelled.swap(false, Ordering::SeqCst)
+  }
+
+  fn cancel(&self) {
+    if self.initializing.load(Ordering::SeqCst) {
+      self.cancelled.store(true, Ordering::SeqCst);
+    }
+  }
+}
+
 #[derive(Clone)]
 struct RefCountDocumentHandler(Arc<dyn DocumentEditor>);
 
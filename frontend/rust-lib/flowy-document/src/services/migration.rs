@@ -3,7 +3,7 @@ use crate::services::rev_sqlite::{DeltaRevisionSql, SQLiteDocumentRevisionPersis
 use crate::DocumentDatabase;
 use bytes::Bytes;
 use flowy_client_sync::make_operations_from_revisions;
-use flowy_error::FlowyResult;
+use flowy_error::{FlowyError, FlowyResult};
 use flowy_revision_persistence::{RevisionDiskCache, SyncRecord};
 use flowy_sqlite::kv::KV;
 use lib_infra::util::md5;
@@ -11,6 +11,9 @@ use revision_model::Revision;
 use std::sync::Arc;
 
 const V1_MIGRATION: &str = "DOCUMENT_V1_MIGRATION";
+/// The number of revision records written to disk in one batch during the migration.
+const MIGRATION_BATCH_SIZE: usize = 200;
+
 pub(crate) struct DocumentMigration {
   user_id: String,
   database: Arc<dyn DocumentDatabase>,
@@ -33,27 +36,38 @@ impl DocumentMigration {
     let disk_cache = SQLiteDocumentRevisionPersistence::new(&self.user_id, pool);
     let documents = DeltaRevisionSql::read_all_documents(&self.user_id, conn)?;
     tracing::debug!("[Migration]: try migrate {} documents", documents.len());
+    let mut records = Vec::with_capacity(MIGRATION_BATCH_SIZE);
+    let mut failures = vec![];
+    let create_records = |records: Vec<SyncRecord>| disk_cache.create_revision_records(records);
     for revisions in documents {
       if revisions.is_empty() {
         continue;
       }
 
       let document_id = revisions.first().unwrap().object_id.clone();
+      // Skip the documents that already have V1 revisions. They were migrated by an earlier
+      // run that didn't finish and may have been edited since then, so migrating them again
+      // would add a second initial revision.
+      match disk_cache.read_revision_records(&document_id, None) {
+        Ok(existing) if !existing.is_empty() => continue,
+        Ok(_) => {},
+        Err(err) => {
+          failures.push((document_id, err));
+          continue;
+        },
+      }
+
       if let Ok(delta) = make_operations_from_revisions(revisions) {
         match DeltaRevisionMigration::run(delta) {
           Ok(transaction) => {
             let bytes = Bytes::from(transaction.to_bytes()?);
             let md5 = format!("{:x}", md5::compute(&bytes));
             let revision = Revision::new(&document_id, 0, 1, bytes, md5);
-            let record = SyncRecord::new(revision);
-            match disk_cache.create_revision_records(vec![record]) {
-              Ok(_) => {},
-              Err(err) => {
-                tracing::error!(
-                  "[Document Migration]: Save revisions to disk failed {:?}",
-                  err
-                );
-              },
+            records.push(SyncRecord::new(revision));
+            if records.len() >= MIGRATION_BATCH_SIZE {
+              let records =
+                std::mem::replace(&mut records, Vec::with_capacity(MIGRATION_BATCH_SIZE));
+              failures.extend(create_records_in_batch(records, create_records));
             }
           },
           Err(err) => {
@@ -65,12 +79,218 @@ impl DocumentMigration {
         }
       }
     }
-    //
+
+    if !records.is_empty() {
+      failures.extend(create_records_in_batch(records, create_records));
+    }
+
+    // Leave the flag unset so that the failed documents are migrated again next time. The
+    // documents that were migrated are skipped on the next run.
+    if !failures.is_empty() {
+      for (document_id, err) in &failures {
+        tracing::error!(
+          "[Document Migration]: Save {} revision to disk failed {:?}",
+          document_id,
+          err
+        );
+      }
+      return Err(
+        FlowyError::internal().context(format!("{} documents failed to migrate", failures.len())),
+      );
+    }
 
     KV::set_bool(&key, true);
     Ok(())
   }
 }
+
+/// Writes the records in one batch. If the batch fails, the records are written one by one
+/// so that one bad record doesn't discard the others. Returns the object id and the error of
+/// every record that couldn't be written.
+fn create_records_in_batch<F>(records: Vec<SyncRecord>, create: F) -> Vec<(String, FlowyError)>
+where
+  F: Fn(Vec<SyncRecord>) -> FlowyResult<()>,
+{
+  let err = match create(records.clone()) {
+    Ok(_) => return vec![],
+    Err(err) => err,
+  };
+  tracing::warn!(
+    "[Document Migration]: Save {} revisions to disk failed {:?}, retry one by one",
+    records.len(),
+    err
+  );
+  records
+    .into_iter()
+    .flat_map(|record| {
+      let object_id = record.revision.object_id.clone();
+      create(vec![record]).err().map(|err| (object_id, err))
+    })
+    .collect()
+}
+
 fn migration_flag_key(user_id: &str, version: &str) -> String {
   md5(format!("{}{}", user_id, version,))
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::services::migration::{
+    create_records_in_batch, migration_flag_key, DocumentMigration, V1_MIGRATION,
+  };
+  use crate::services::rev_sqlite::{
+    SQLiteDeltaDocumentRevisionPersistence, SQLiteDocumentRevisionPersistence,
+  };
+  use crate::DocumentDatabase;
+  use bytes::Bytes;
+  use flowy_error::FlowyError;
+  use flowy_revision_persistence::{RevisionDiskCache, SyncRecord};
+  use flowy_sqlite::kv::KV;
+  use flowy_sqlite::ConnectionPool;
+  use revision_model::Revision;
+  use std::sync::{Arc, Mutex};
+
+  #[test]
+  fn create_records_in_batch_test() {
+    let disk = Mutex::new(vec![]);
+    let failures = create_records_in_batch(make_records(5), |records| {
+      disk.lock().unwrap().extend(records);
+      Ok(())
+    });
+    assert!(failures.is_empty());
+    assert_eq!(disk.lock().unwrap().len(), 5);
+  }
+
+  #[test]
+  fn create_records_in_batch_with_bad_record_test() {
+    let disk = Mutex::new(vec![]);
+    // Writing fails as a whole whenever the batch contains doc_2, just like a sqlite insert
+    // of many rows fails when one of them violates a constraint.
+    let failures = create_records_in_batch(make_records(5), |records| {
+      if records
+        .iter()
+        .any(|record| record.revision.object_id == "doc_2")
+      {
+        return Err(FlowyError::internal().context("constraint violation"));
+      }
+      disk.lock().unwrap().extend(records);
+      Ok(())
+    });
+
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].0, "doc_2");
+    let written = disk
+      .lock()
+      .unwrap()
+      .iter()
+      .map(|record: &SyncRecord| record.revision.object_id.clone())
+      .collect::<Vec<_>>();
+    assert_eq!(written, vec!["doc_0", "doc_1", "doc_3", "doc_4"]);
+  }
+
+  #[test]
+  fn migrate_documents_in_batches_test() {
+    let database = TestDatabase::new("migrate_documents_in_batches_test", 2000);
+    DocumentMigration::new(&database.user_id, database.clone())
+      .run_v1_migration()
+      .unwrap();
+
+    for i in 0..2000 {
+      let records = database.read_v1_records(&format!("doc_{}", i));
+      assert_eq!(records.len(), 1);
+      assert_eq!(records[0].revision.rev_id, 1);
+    }
+  }
+
+  #[test]
+  fn migrate_documents_twice_test() {
+    let database = TestDatabase::new("migrate_documents_twice_test", 3);
+    let migration = DocumentMigration::new(&database.user_id, database.clone());
+    migration.run_v1_migration().unwrap();
+
+    // The document is edited in V1 after it was migrated.
+    let revision = Revision::new("doc_0", 1, 2, Bytes::from("edit"), "");
+    database
+      .v1_disk_cache()
+      .create_revision_records(vec![SyncRecord::new(revision)])
+      .unwrap();
+
+    // Clear the flag as if the first run had failed to migrate one of the documents.
+    KV::remove(&migration_flag_key(&database.user_id, V1_MIGRATION)).unwrap();
+    migration.run_v1_migration().unwrap();
+
+    let rev_ids = |doc_id: &str| {
+      database
+        .read_v1_records(doc_id)
+        .iter()
+        .map(|record| record.revision.rev_id)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(rev_ids("doc_0"), vec![1, 2]);
+    assert_eq!(rev_ids("doc_1"), vec![1]);
+    assert_eq!(rev_ids("doc_2"), vec![1]);
+  }
+
+  fn make_records(count: u8) -> Vec<SyncRecord> {
+    (0..count)
+      .map(|i| {
+        let revision = Revision::new(&format!("doc_{}", i), 0, 1, vec![i].into(), "".to_owned());
+        SyncRecord::new(revision)
+      })
+      .collect()
+  }
+
+  struct TestDatabase {
+    user_id: String,
+    pool: Arc<ConnectionPool>,
+  }
+
+  impl TestDatabase {
+    /// Creates a database under the temp dir with `document_count` documents in the V0
+    /// revision table.
+    fn new(name: &str, document_count: usize) -> Arc<Self> {
+      let root = std::env::temp_dir().join("flowy_document_migration");
+      let kv_dir = root.join("kv");
+      std::fs::create_dir_all(&kv_dir).unwrap();
+      KV::init(kv_dir.to_str().unwrap()).unwrap();
+
+      let dir = root.join(name);
+      let _ = std::fs::remove_dir_all(&dir);
+      let pool = flowy_sqlite::init(dir.to_str().unwrap())
+        .unwrap()
+        .get_pool();
+      let user_id = name.to_owned();
+      let records = (0..document_count)
+        .map(|i| {
+          let bytes = Bytes::from(r#"[{"insert":"Hello world\n"}]"#);
+          let revision = Revision::new(&format!("doc_{}", i), 0, 1, bytes, "");
+          SyncRecord::new(revision)
+        })
+        .collect::<Vec<_>>();
+      SQLiteDeltaDocumentRevisionPersistence::new(&user_id, pool.clone())
+        .create_revision_records(records)
+        .unwrap();
+
+      // Clear the flag left over from a previous run of the test.
+      KV::remove(&migration_flag_key(&user_id, V1_MIGRATION)).unwrap();
+      Arc::new(Self { user_id, pool })
+    }
+
+    fn v1_disk_cache(&self) -> SQLiteDocumentRevisionPersistence {
+      SQLiteDocumentRevisionPersistence::new(&self.user_id, self.pool.clone())
+    }
+
+    fn read_v1_records(&self, doc_id: &str) -> Vec<SyncRecord> {
+      self
+        .v1_disk_cache()
+        .read_revision_records(doc_id, None)
+        .unwrap()
+    }
+  }
+
+  impl DocumentDatabase for TestDatabase {
+    fn db_pool(&self) -> Result<Arc<ConnectionPool>, FlowyError> {
+      Ok(self.pool.clone())
+    }
+  }
+}
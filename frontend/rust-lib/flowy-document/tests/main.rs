@@ -1,3 +1,4 @@
 mod editor;
 mod new_document;
+mod proto_compat;
 // mod old_document;
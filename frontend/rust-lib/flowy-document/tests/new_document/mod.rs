@@ -1,3 +1,4 @@
 mod document_compose_test;
 mod script;
 mod test;
+mod span_test;
@@ -16,7 +16,6 @@ pub enum EditScript {
     path: Path,
     delta: DeltaTextOperations,
   },
-  #[allow(dead_code)]
   ComposeTransaction {
     transaction: Transaction,
   },
@@ -36,6 +35,7 @@ pub enum EditScript {
 
 pub struct DocumentEditorTest {
   pub sdk: FlowySDKTest,
+  pub view_id: String,
   pub editor: Arc<AppFlowyDocumentEditor>,
 }
 
@@ -59,7 +59,11 @@ impl DocumentEditorTest {
       Some(editor) => editor.clone(),
     };
 
-    Self { sdk, editor }
+    Self {
+      sdk,
+      view_id: test.view.id,
+      editor,
+    }
   }
 
   pub async fn run_scripts(&self, scripts: Vec<EditScript>) {
@@ -0,0 +1,137 @@
+use crate::new_document::script::DocumentEditorTest;
+use crate::new_document::script::EditScript::*;
+use lib_ot::text_delta::DeltaTextOperationBuilder;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::Registry;
+
+#[tokio::test]
+async fn document_open_and_edit_spans_test() {
+  let recorder = SpanRecorder::default();
+  let _guard = tracing::subscriber::set_default(Registry::default().with(recorder.clone()));
+
+  let test = DocumentEditorTest::new().await;
+  let scripts = vec![
+    UpdateText {
+      path: vec![0, 0].into(),
+      delta: DeltaTextOperationBuilder::new()
+        .insert("Hello world")
+        .build(),
+    },
+    AssertContent {
+      expected: r#"{"document":{"type":"editor","children":[{"type":"text","delta":[{"insert":"Hello world"}]}]}}"#,
+    },
+  ];
+  test.run_scripts(scripts).await;
+
+  let doc_id = test.view_id.as_str();
+  recorder.assert_field("open_document_editor", "document_id", doc_id);
+  recorder.assert_field("apply_transaction", "doc_id", doc_id);
+  recorder.assert_field("get_content", "doc_id", doc_id);
+  recorder.assert_non_zero_bytes("save_local_operations");
+  recorder.assert_non_zero_bytes("get_content");
+}
+
+#[derive(Debug)]
+struct RecordedSpan {
+  name: &'static str,
+  fields: HashMap<&'static str, String>,
+}
+
+impl Visit for RecordedSpan {
+  fn record_i64(&mut self, field: &Field, value: i64) {
+    self.fields.insert(field.name(), value.to_string());
+  }
+
+  fn record_u64(&mut self, field: &Field, value: u64) {
+    self.fields.insert(field.name(), value.to_string());
+  }
+
+  fn record_str(&mut self, field: &Field, value: &str) {
+    self.fields.insert(field.name(), value.to_string());
+  }
+
+  fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+    self.fields.insert(field.name(), format!("{:?}", value));
+  }
+}
+
+/// Records the name and fields of every span created on the current thread.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+  inner: Arc<Mutex<SpanRecorderInner>>,
+}
+
+#[derive(Default)]
+struct SpanRecorderInner {
+  spans: Vec<RecordedSpan>,
+  // Span ids may be reused after a span is closed, so map them to the latest span.
+  indexes: HashMap<u64, usize>,
+}
+
+impl SpanRecorder {
+  fn spans_named(&self, name: &str) -> Vec<HashMap<&'static str, String>> {
+    let inner = self.inner.lock().unwrap();
+    let spans = inner
+      .spans
+      .iter()
+      .filter(|span| span.name == name)
+      .map(|span| span.fields.clone())
+      .collect::<Vec<_>>();
+    assert!(!spans.is_empty(), "no {} span was recorded", name);
+    spans
+  }
+
+  fn assert_field(&self, name: &str, field: &str, expected: &str) {
+    let spans = self.spans_named(name);
+    assert!(
+      spans
+        .iter()
+        .any(|fields| fields.get(field).map(|value| value.as_str()) == Some(expected)),
+      "{} spans {:?} don't have {} = {}",
+      name,
+      spans,
+      field,
+      expected
+    );
+  }
+
+  fn assert_non_zero_bytes(&self, name: &str) {
+    let spans = self.spans_named(name);
+    assert!(
+      spans.iter().any(|fields| fields
+        .get("bytes")
+        .and_then(|value| value.parse::<u64>().ok())
+        .map_or(false, |bytes| bytes > 0)),
+      "{} spans {:?} don't record any bytes",
+      name,
+      spans
+    );
+  }
+}
+
+impl<S: Subscriber> Layer<S> for SpanRecorder {
+  fn new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+    let mut span = RecordedSpan {
+      name: attrs.metadata().name(),
+      fields: HashMap::new(),
+    };
+    attrs.record(&mut span);
+
+    let mut inner = self.inner.lock().unwrap();
+    let index = inner.spans.len();
+    inner.spans.push(span);
+    inner.indexes.insert(id.into_u64(), index);
+  }
+
+  fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+    let mut inner = self.inner.lock().unwrap();
+    if let Some(index) = inner.indexes.get(&id.into_u64()).copied() {
+      values.record(&mut inner.spans[index]);
+    }
+  }
+}
@@ -1,7 +1,17 @@
 use crate::new_document::script::DocumentEditorTest;
 use crate::new_document::script::EditScript::*;
-
+use flowy_document::editor::Document;
+use flowy_document::entities::{
+  EditParams, EditPayloadPB, ExportParams, ExportPayloadPB, OpenDocumentParams,
+  OpenDocumentPayloadPB,
+};
+use flowy_document::errors::ErrorCode;
+use flowy_revision::REVISION_WRITE_INTERVAL_IN_MILLIS;
+use lib_ot::core::Transaction;
 use lib_ot::text_delta::DeltaTextOperationBuilder;
+use std::convert::TryInto;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[tokio::test]
 async fn document_initialize_test() {
@@ -162,3 +172,135 @@ async fn document_delete_node_test() {
 
   DocumentEditorTest::new().await.run_scripts(scripts).await;
 }
+
+#[tokio::test]
+async fn document_invalid_id_test() {
+  let test = DocumentEditorTest::new().await;
+  let manager = &test.sdk.document_manager;
+  let cases = vec![
+    ("".to_string(), ErrorCode::DocumentIdIsEmpty),
+    ("   ".to_string(), ErrorCode::DocumentIdIsEmpty),
+    ("a".repeat(5000), ErrorCode::DocumentIdTooLong),
+  ];
+
+  for (document_id, code) in cases {
+    let error = manager
+      .open_document_editor(&document_id)
+      .await
+      .err()
+      .unwrap();
+    assert_eq!(error.code, code.value());
+
+    let error = manager
+      .create_document(&document_id, vec![])
+      .await
+      .unwrap_err();
+    assert_eq!(error.code, code.value());
+
+    let error = manager
+      .close_document_editor(&document_id)
+      .await
+      .unwrap_err();
+    assert_eq!(error.code, code.value());
+
+    let params: Result<EditParams, ErrorCode> = EditPayloadPB {
+      doc_id: document_id.clone(),
+      operations: "[]".to_string(),
+    }
+    .try_into();
+    assert_eq!(params.err().unwrap(), code);
+
+    let params: Result<ExportParams, ErrorCode> = ExportPayloadPB {
+      view_id: document_id.clone(),
+      ..Default::default()
+    }
+    .try_into();
+    assert_eq!(params.err().unwrap(), code);
+
+    let params: Result<OpenDocumentParams, ErrorCode> = OpenDocumentPayloadPB {
+      document_id: document_id.clone(),
+      ..Default::default()
+    }
+    .try_into();
+    assert_eq!(params.err().unwrap(), code);
+  }
+}
+
+#[tokio::test]
+async fn document_skip_saving_unchanged_content_test() {
+  let test = DocumentEditorTest::new().await;
+  let rev_id = test.editor.rev_id();
+
+  // A transaction that doesn't change the content shouldn't be saved as a new revision.
+  test
+    .run_scripts(vec![ComposeTransaction {
+      transaction: Transaction::new(),
+    }])
+    .await;
+  assert_eq!(test.editor.rev_id(), rev_id);
+
+  test
+    .run_scripts(vec![UpdateText {
+      path: vec![0, 0].into(),
+      delta: DeltaTextOperationBuilder::new()
+        .insert("Hello world")
+        .build(),
+    }])
+    .await;
+  assert_eq!(test.editor.rev_id(), rev_id + 1);
+
+  // Reopen the document and check the edit was persisted.
+  let manager = &test.sdk.document_manager;
+  manager.close_document_editor(&test.view_id).await.unwrap();
+  let milliseconds = 2 * REVISION_WRITE_INTERVAL_IN_MILLIS;
+  tokio::time::sleep(Duration::from_millis(milliseconds)).await;
+  let editor = manager.open_document_editor(&test.view_id).await.unwrap();
+
+  let expected = r#"{"document":{"type":"editor","children":[{"type":"text","delta":[{"insert":"Hello world"}]}]}}"#;
+  let expected_document: Document = serde_json::from_str(expected).unwrap();
+  let expected = serde_json::to_string(&expected_document).unwrap();
+  assert_eq!(editor.export().await.unwrap(), expected);
+}
+
+#[tokio::test]
+async fn document_open_twice_test() {
+  let test = DocumentEditorTest::new().await;
+  let manager = &test.sdk.document_manager;
+  let first = manager.open_document_editor(&test.view_id).await.unwrap();
+  let second = manager.open_document_editor(&test.view_id).await.unwrap();
+  assert!(Arc::ptr_eq(&first, &second));
+
+  // The document stays opened until every open of it is closed.
+  for _ in 0..2 {
+    manager.close_document_editor(&test.view_id).await.unwrap();
+    assert!(manager.is_document_opened(&test.view_id).await);
+  }
+  manager.close_document_editor(&test.view_id).await.unwrap();
+  assert!(!manager.is_document_opened(&test.view_id).await);
+}
+
+#[tokio::test]
+async fn document_interleaved_open_and_close_test() {
+  let test = DocumentEditorTest::new().await;
+  let manager = &test.sdk.document_manager;
+  manager.close_document_editor(&test.view_id).await.unwrap();
+
+  for i in 0..100 {
+    // Start each pair from a fresh poll so that the open always runs before the close.
+    tokio::task::yield_now().await;
+    let open = manager.open_document_editor(&test.view_id);
+    let close = async {
+      for _ in 0..i % 3 {
+        tokio::task::yield_now().await;
+      }
+      manager.close_document_editor(&test.view_id).await
+    };
+    let (open, close) = tokio::join!(open, close);
+    close.unwrap();
+    // The open either completes before the close or is cancelled by it.
+    if let Err(error) = open {
+      assert_eq!(error.code, ErrorCode::DocumentOpenCancelled.value());
+    }
+    assert!(!manager.is_document_opened(&test.view_id).await);
+  }
+}
@@ -0,0 +1,142 @@
+use bytes::Bytes;
+use flowy_document::entities::{
+  DocumentDataPB, DocumentVersionPB, EditPayloadPB, ExportDataPB, ExportPayloadPB, ExportType,
+  OpenDocumentPayloadPB,
+};
+use protobuf::Message;
+use std::convert::{TryFrom, TryInto};
+
+// Golden bytes of the document messages that are exchanged with the Dart side. Changing a
+// field number or a field type makes these tests fail. If a change is intentional, run
+// `cargo test proto_compat -- --ignored --nocapture` and copy the printed fixtures here.
+const EDIT_PAYLOAD: &[u8] = b"\x0a\x05doc_1\x12\x02[]";
+const DOCUMENT_DATA: &[u8] = b"\x0a\x05doc_1\x12\x02{}";
+const OPEN_DOCUMENT_PAYLOAD: &[u8] = b"\x0a\x05doc_1\x10\x01";
+const EXPORT_PAYLOAD: &[u8] = b"\x0a\x05doc_1\x10\x01\x18\x01";
+const EXPORT_DATA: &[u8] = b"\x0a\x04# Hi\x10\x01";
+
+// Field 15 is not used by any of the messages above. It stands for a field that a newer
+// client might add.
+const FUTURE_FIELD: &[u8] = b"\x7a\x06future";
+
+fn edit_payload() -> EditPayloadPB {
+  EditPayloadPB {
+    doc_id: "doc_1".to_string(),
+    operations: "[]".to_string(),
+  }
+}
+
+fn document_data() -> DocumentDataPB {
+  DocumentDataPB {
+    doc_id: "doc_1".to_string(),
+    content: "{}".to_string(),
+  }
+}
+
+fn open_document_payload() -> OpenDocumentPayloadPB {
+  OpenDocumentPayloadPB {
+    document_id: "doc_1".to_string(),
+    version: DocumentVersionPB::V1,
+  }
+}
+
+fn export_payload() -> ExportPayloadPB {
+  ExportPayloadPB {
+    view_id: "doc_1".to_string(),
+    export_type: ExportType::Markdown,
+    document_version: DocumentVersionPB::V1,
+  }
+}
+
+fn export_data() -> ExportDataPB {
+  ExportDataPB {
+    data: "# Hi".to_string(),
+    export_type: ExportType::Markdown,
+  }
+}
+
+fn encode<T: TryInto<Bytes, Error = protobuf::ProtobufError>>(message: T) -> Vec<u8> {
+  message.try_into().unwrap().to_vec()
+}
+
+#[test]
+fn edit_payload_wire_compat_test() {
+  let payload = EditPayloadPB::try_from(EDIT_PAYLOAD).unwrap();
+  assert_eq!(payload.doc_id, "doc_1");
+  assert_eq!(payload.operations, "[]");
+  assert_eq!(encode(payload), EDIT_PAYLOAD);
+  assert_eq!(encode(edit_payload()), EDIT_PAYLOAD);
+}
+
+#[test]
+fn document_data_wire_compat_test() {
+  let data = DocumentDataPB::try_from(DOCUMENT_DATA).unwrap();
+  assert_eq!(data.doc_id, "doc_1");
+  assert_eq!(data.content, "{}");
+  assert_eq!(encode(data), DOCUMENT_DATA);
+  assert_eq!(encode(document_data()), DOCUMENT_DATA);
+}
+
+#[test]
+fn open_document_payload_wire_compat_test() {
+  let payload = OpenDocumentPayloadPB::try_from(OPEN_DOCUMENT_PAYLOAD).unwrap();
+  assert_eq!(payload.document_id, "doc_1");
+  assert_eq!(payload.version, DocumentVersionPB::V1);
+  assert_eq!(encode(payload), OPEN_DOCUMENT_PAYLOAD);
+  assert_eq!(encode(open_document_payload()), OPEN_DOCUMENT_PAYLOAD);
+}
+
+#[test]
+fn export_payload_wire_compat_test() {
+  let payload = ExportPayloadPB::try_from(EXPORT_PAYLOAD).unwrap();
+  assert_eq!(payload.view_id, "doc_1");
+  assert_eq!(payload.export_type, ExportType::Markdown);
+  assert_eq!(payload.document_version, DocumentVersionPB::V1);
+  assert_eq!(encode(payload), EXPORT_PAYLOAD);
+  assert_eq!(encode(export_payload()), EXPORT_PAYLOAD);
+}
+
+#[test]
+fn export_data_wire_compat_test() {
+  let data = ExportDataPB::try_from(EXPORT_DATA).unwrap();
+  assert_eq!(data.data, "# Hi");
+  assert_eq!(data.export_type, ExportType::Markdown);
+  assert_eq!(encode(data), EXPORT_DATA);
+  assert_eq!(encode(export_data()), EXPORT_DATA);
+}
+
+#[test]
+fn unknown_field_round_trip_test() {
+  fn assert_round_trip<M: Message>(fixture: &[u8]) {
+    let bytes = [fixture, FUTURE_FIELD].concat();
+    let message = M::parse_from_bytes(&bytes).unwrap();
+    assert_eq!(message.write_to_bytes().unwrap(), bytes);
+  }
+
+  use flowy_document::protobuf as pb;
+  assert_round_trip::<pb::EditPayloadPB>(EDIT_PAYLOAD);
+  assert_round_trip::<pb::DocumentDataPB>(DOCUMENT_DATA);
+  assert_round_trip::<pb::OpenDocumentPayloadPB>(OPEN_DOCUMENT_PAYLOAD);
+  assert_round_trip::<pb::ExportPayloadPB>(EXPORT_PAYLOAD);
+  assert_round_trip::<pb::ExportDataPB>(EXPORT_DATA);
+}
+
+#[test]
+#[ignore]
+fn print_golden_fixtures() {
+  let fixtures = vec![
+    ("EDIT_PAYLOAD", encode(edit_payload())),
+    ("DOCUMENT_DATA", encode(document_data())),
+    ("OPEN_DOCUMENT_PAYLOAD", encode(open_document_payload())),
+    ("EXPORT_PAYLOAD", encode(export_payload())),
+    ("EXPORT_DATA", encode(export_data())),
+  ];
+  for (name, bytes) in fixtures {
+    let literal: String = bytes
+      .into_iter()
+      .flat_map(std::ascii::escape_default)
+      .map(char::from)
+      .collect();
+    println!("const {}: &[u8] = b\"{}\";", name, literal);
+  }
+}
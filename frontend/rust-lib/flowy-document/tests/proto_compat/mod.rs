@@ -0,0 +1 @@
+mod golden_test;
@@ -189,6 +189,15 @@ pub enum ErrorCode {
 
   #[error("Only the date type can be used in calendar")]
   UnexpectedCalendarFieldType = 61,
+
+  #[error("Document id is empty")]
+  DocumentIdIsEmpty = 62,
+
+  #[error("Document id is too long")]
+  DocumentIdTooLong = 63,
+
+  #[error("Document was closed before it finished opening")]
+  DocumentOpenCancelled = 64,
 }
 
 impl ErrorCode {